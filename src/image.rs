@@ -0,0 +1,290 @@
+//! Turns raw SANE frame data into a [`DecodedImage`], so callers don't have
+//! to re-implement SANE's frame/depth rules on top of `sys::Parameters`
+//! themselves.
+//!
+//! Single-pass scanners (`Frame::Gray`, `Frame::Rgb`) are decoded directly.
+//! Three-pass scanners deliver `Frame::Red`, `Frame::Green` and `Frame::Blue`
+//! as separate, sequential frames — [`read_image`] drives `start_scan`/`read`
+//! across all of them and interleaves the planes into one RGB buffer.
+
+use crate::{sys, DeviceHandle, Error, Result};
+
+/// A decoded scan, with row padding stripped and depth normalized to whole
+/// bytes per sample.
+#[derive(Debug, Clone)]
+pub struct DecodedImage {
+	pub width: u32,
+	pub height: u32,
+	/// Number of samples per pixel (1 for gray, 3 for RGB).
+	pub channels: u8,
+	/// Bits per sample, as reported by the backend (1, 8 or 16).
+	pub depth: u8,
+	/// Packed `height * width * channels` samples, `depth` bits each (1-bit
+	/// samples are expanded to one byte per sample; 16-bit samples are kept
+	/// host-endian, two bytes per sample).
+	pub data: Vec<u8>,
+}
+
+/// Drives `handle` through one or more frames (`start_scan`/`read` per SANE's
+/// single-pass or three-pass protocol) and decodes the result.
+///
+/// `handle` must not already be mid-scan; this calls `start_scan` itself,
+/// once per frame.
+pub fn read_image(handle: &mut DeviceHandle) -> Result<DecodedImage> {
+	let mut width = 0_u32;
+	let mut height = 0_u32;
+	let mut depth = 0_u8;
+
+	let mut gray = None;
+	let mut rgb = None;
+	let mut planes: [Option<Vec<u8>>; 3] = [None, None, None];
+
+	loop {
+		let parameters = handle.start_scan()?;
+		width = parameters.pixels_per_line as u32;
+		depth = parameters.depth as u8;
+
+		let raw = handle.read_to_vec()?;
+		let channels = if parameters.format == sys::Frame::Rgb {
+			3
+		} else {
+			1
+		};
+		let lines = resolve_lines(
+			parameters.lines,
+			parameters.bytes_per_line as usize,
+			raw.len(),
+		)?;
+		height = lines as u32;
+		let unpacked = unpack_rows(&raw, &parameters, channels, lines)?;
+
+		match parameters.format {
+			sys::Frame::Gray => gray = Some(unpacked),
+			sys::Frame::Rgb => rgb = Some(unpacked),
+			sys::Frame::Red => planes[0] = Some(unpacked),
+			sys::Frame::Green => planes[1] = Some(unpacked),
+			sys::Frame::Blue => planes[2] = Some(unpacked),
+		}
+
+		if parameters.last_frame != 0 {
+			break;
+		}
+	}
+
+	if let Some(data) = rgb {
+		return Ok(DecodedImage {
+			width,
+			height,
+			channels: 3,
+			depth,
+			data,
+		});
+	}
+	if let Some(data) = gray {
+		return Ok(DecodedImage {
+			width,
+			height,
+			channels: 1,
+			depth,
+			data,
+		});
+	}
+
+	let [r, g, b] = planes;
+	let (r, g, b) = match (r, g, b) {
+		(Some(r), Some(g), Some(b)) => (r, g, b),
+		_ => return Err(Error(sys::Status::Inval)),
+	};
+	let sample_len = if depth == 16 { 2 } else { 1 };
+	let mut data = Vec::with_capacity(r.len() * 3);
+	for i in (0..r.len()).step_by(sample_len) {
+		data.extend_from_slice(&r[i..][..sample_len]);
+		data.extend_from_slice(&g[i..][..sample_len]);
+		data.extend_from_slice(&b[i..][..sample_len]);
+	}
+	Ok(DecodedImage {
+		width,
+		height,
+		channels: 3,
+		depth,
+		data,
+	})
+}
+
+/// Resolves `Parameters::lines` to an actual row count. SANE allows `lines ==
+/// -1` for backends (e.g. hand-fed/sheet-fed scanners) that don't know the
+/// frame height in advance; in that case the row count is derived from how
+/// much data actually came back instead.
+///
+/// Either way, the result is checked against `raw_len`: a device that
+/// short-reads (paper jam, flaky USB, an early `Status::Eof`) must not send
+/// [`unpack_rows`] indexing past the end of the buffer.
+fn resolve_lines(lines: i32, bytes_per_line: usize, raw_len: usize) -> Result<usize> {
+	let lines = if lines >= 0 {
+		lines as usize
+	} else if bytes_per_line == 0 {
+		return Err(Error(sys::Status::Inval));
+	} else {
+		raw_len / bytes_per_line
+	};
+
+	if raw_len < lines * bytes_per_line {
+		return Err(Error(sys::Status::IoError));
+	}
+	Ok(lines)
+}
+
+/// Strips `bytes_per_line` row padding and normalizes `depth` to whole bytes
+/// per sample (1-bit rows are MSB-packed and get expanded one bit per byte).
+fn unpack_rows(
+	raw: &[u8],
+	parameters: &sys::Parameters,
+	channels: u8,
+	lines: usize,
+) -> Result<Vec<u8>> {
+	let bytes_per_line = parameters.bytes_per_line as usize;
+	let pixels_per_line = parameters.pixels_per_line as usize;
+	let samples_per_line = pixels_per_line * channels as usize;
+
+	match parameters.depth {
+		1 => {
+			let mut out = Vec::with_capacity(samples_per_line * lines);
+			for line in 0..lines {
+				let row = &raw[line * bytes_per_line..][..bytes_per_line];
+				for sample_idx in 0..samples_per_line {
+					let byte = row[sample_idx / 8];
+					let bit = 7 - (sample_idx % 8);
+					out.push(if (byte >> bit) & 1 == 1 { 0xff } else { 0x00 });
+				}
+			}
+			Ok(out)
+		}
+		8 => {
+			let mut out = Vec::with_capacity(samples_per_line * lines);
+			for line in 0..lines {
+				let row = &raw[line * bytes_per_line..][..samples_per_line];
+				out.extend_from_slice(row);
+			}
+			Ok(out)
+		}
+		16 => {
+			let row_bytes = samples_per_line * 2;
+			let mut out = Vec::with_capacity(row_bytes * lines);
+			for line in 0..lines {
+				let row = &raw[line * bytes_per_line..][..row_bytes];
+				for sample in row.chunks_exact(2) {
+					out.extend_from_slice(&u16::from_ne_bytes([sample[0], sample[1]]).to_ne_bytes());
+				}
+			}
+			Ok(out)
+		}
+		_ => Err(Error(sys::Status::Inval)),
+	}
+}
+
+#[cfg(feature = "image")]
+impl DecodedImage {
+	/// Converts this buffer into an `image` crate [`image::DynamicImage`],
+	/// ready for PNG/TIFF/etc. encoding. Returns `None` for
+	/// channel/depth combinations `image` has no `DynamicImage` variant for.
+	pub fn into_dynamic_image(self) -> Option<image::DynamicImage> {
+		use image::{DynamicImage, ImageBuffer, Luma, Rgb};
+
+		match (self.channels, self.depth) {
+			(1, 8) => ImageBuffer::<Luma<u8>, _>::from_raw(self.width, self.height, self.data)
+				.map(DynamicImage::ImageLuma8),
+			(3, 8) => ImageBuffer::<Rgb<u8>, _>::from_raw(self.width, self.height, self.data)
+				.map(DynamicImage::ImageRgb8),
+			(1, 16) => ImageBuffer::<Luma<u16>, _>::from_raw(
+				self.width,
+				self.height,
+				to_u16_samples(&self.data),
+			)
+			.map(DynamicImage::ImageLuma16),
+			(3, 16) => ImageBuffer::<Rgb<u16>, _>::from_raw(
+				self.width,
+				self.height,
+				to_u16_samples(&self.data),
+			)
+			.map(DynamicImage::ImageRgb16),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(feature = "image")]
+fn to_u16_samples(data: &[u8]) -> Vec<u16> {
+	data.chunks_exact(2)
+		.map(|sample| u16::from_ne_bytes([sample[0], sample[1]]))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn params(format: sys::Frame, depth: i32, pixels_per_line: i32, bytes_per_line: i32) -> sys::Parameters {
+		sys::Parameters {
+			format,
+			last_frame: 1,
+			depth,
+			pixels_per_line,
+			bytes_per_line,
+			lines: 0,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn unpack_rows_strips_padding_at_depth_8() {
+		// 3 pixels/line but 4 bytes/line (1 byte of padding), 2 lines, 1 channel.
+		let raw = vec![1, 2, 3, 0xAA, 4, 5, 6, 0xBB];
+		let parameters = params(sys::Frame::Gray, 8, 3, 4);
+		let out = unpack_rows(&raw, &parameters, 1, 2).unwrap();
+		assert_eq!(out, vec![1, 2, 3, 4, 5, 6]);
+	}
+
+	#[test]
+	fn unpack_rows_expands_1_bit_msb_first() {
+		// One line, 8 pixels/line, MSB-first: 0b1010_0001.
+		let raw = vec![0b1010_0001];
+		let parameters = params(sys::Frame::Gray, 1, 8, 1);
+		let out = unpack_rows(&raw, &parameters, 1, 1).unwrap();
+		assert_eq!(
+			out,
+			vec![0xff, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff]
+		);
+	}
+
+	#[test]
+	fn unpack_rows_keeps_16_bit_samples_host_endian() {
+		let sample: u16 = 0x1234;
+		let raw = sample.to_ne_bytes().to_vec();
+		let parameters = params(sys::Frame::Gray, 16, 1, 2);
+		let out = unpack_rows(&raw, &parameters, 1, 1).unwrap();
+		assert_eq!(out, sample.to_ne_bytes().to_vec());
+	}
+
+	#[test]
+	fn resolve_lines_passes_through_known_line_count() {
+		assert_eq!(resolve_lines(42, 100, 4200).unwrap(), 42);
+	}
+
+	#[test]
+	fn resolve_lines_derives_from_raw_len_when_unknown() {
+		// lines == -1 (sheet-fed scanner, height unknown upfront).
+		assert_eq!(resolve_lines(-1, 10, 35).unwrap(), 3);
+	}
+
+	#[test]
+	fn resolve_lines_rejects_unknown_with_zero_bytes_per_line() {
+		assert!(resolve_lines(-1, 0, 10).is_err());
+	}
+
+	#[test]
+	fn resolve_lines_rejects_short_read_of_known_line_count() {
+		// Device claimed 42 lines but a paper jam / flaky USB link only
+		// delivered enough bytes for 41.
+		assert!(resolve_lines(42, 100, 41 * 100).is_err());
+	}
+}
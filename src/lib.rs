@@ -2,6 +2,12 @@
 
 pub mod sys;
 
+pub mod image;
+pub mod options;
+
+#[cfg(feature = "tokio")]
+pub mod scan;
+
 pub use sys::*;
 
 use bitflags::bitflags;
@@ -9,6 +15,7 @@ use log::{debug, info};
 use std::ffi::{c_void, CStr, CString};
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::Range;
+use std::os::unix::io::RawFd;
 
 type Result<T> = std::result::Result<T, Error>;
 
@@ -119,6 +126,7 @@ impl Device {
 		Ok(DeviceHandle {
 			handle: device_handle,
 			scanning: false,
+			last_frame: false,
 		})
 	}
 }
@@ -126,6 +134,10 @@ impl Device {
 pub struct DeviceHandle {
 	handle: sys::Handle,
 	scanning: bool,
+	/// Whether the frame currently (or most recently) started is the last
+	/// one in a multi-frame acquisition, per `Parameters::last_frame` from
+	/// the matching `start_scan`.
+	last_frame: bool,
 }
 
 impl Drop for DeviceHandle {
@@ -227,6 +239,7 @@ impl DeviceHandle {
 		let parameters = self.get_parameters()?;
 
 		self.scanning = true;
+		self.last_frame = parameters.last_frame != 0;
 
 		Ok(parameters)
 	}
@@ -243,9 +256,16 @@ impl DeviceHandle {
 		Ok(parameters)
 	}
 
-	pub fn read(&mut self, buf: &mut [u8]) -> Result<Option<usize>> {
+	/// Reads the next chunk of scan data.
+	///
+	/// In blocking mode (the default) this only ever yields [`ReadOutcome::Data`]
+	/// or [`ReadOutcome::Eof`]. In non-blocking mode (see [`Self::set_io_mode`])
+	/// `sane_read` can report [`sys::Status::Good`] with zero bytes written to
+	/// mean "no data yet" — that case is surfaced as [`ReadOutcome::Pending`]
+	/// so callers don't mistake it for the end of the frame.
+	pub fn read(&mut self, buf: &mut [u8]) -> Result<ReadOutcome> {
 		if !self.scanning {
-			return Ok(None);
+			return Ok(ReadOutcome::Eof);
 		}
 		let mut bytes_written = 0_i32;
 		let status = unsafe {
@@ -260,14 +280,20 @@ impl DeviceHandle {
 			sys::Status::Eof => {
 				if bytes_written == 0 {
 					self.scanning = false;
-					unsafe { sys::sane_cancel(self.handle) };
+					// `sane_cancel` resets the whole multi-frame acquisition, so
+					// only call it once the last frame (e.g. the Blue pass of a
+					// three-pass scan) has ended — not between frames.
+					if self.last_frame {
+						unsafe { sys::sane_cancel(self.handle) };
+					}
 
-					Ok(None)
+					Ok(ReadOutcome::Eof)
 				} else {
-					Ok(Some(bytes_written as usize))
+					Ok(ReadOutcome::Data(bytes_written as usize))
 				}
 			}
-			sys::Status::Good => Ok(Some(bytes_written as usize)),
+			sys::Status::Good if bytes_written == 0 => Ok(ReadOutcome::Pending),
+			sys::Status::Good => Ok(ReadOutcome::Data(bytes_written as usize)),
 			status => Err(Error(status)),
 		}
 	}
@@ -284,13 +310,39 @@ impl DeviceHandle {
 			buf.set_len(buf.capacity());
 		}
 
-		while let Ok(Some(written)) = self.read(buf.as_mut_slice()) {
+		while let Ok(ReadOutcome::Data(written)) = self.read(buf.as_mut_slice()) {
 			image.extend_from_slice(&buf[0..written]);
 		}
 
 		Ok(image)
 	}
 
+	/// Switches the device between blocking and non-blocking acquisition
+	/// (`sane_set_io_mode`). Once in non-blocking mode, [`Self::read`] can
+	/// return [`ReadOutcome::Pending`] instead of blocking until data arrives;
+	/// pair it with [`Self::get_select_fd`] to know when to retry.
+	pub fn set_io_mode(&mut self, non_blocking: bool) -> Result<()> {
+		let status =
+			unsafe { sys::sane_set_io_mode(self.handle, non_blocking as sys::Bool) };
+		if status != sys::Status::Good {
+			return Err(Error(status));
+		}
+		Ok(())
+	}
+
+	/// Returns the file descriptor backends expose for event-loop integration
+	/// (`sane_get_select_fd`), or `None` when the backend doesn't support
+	/// non-blocking acquisition (`Status::Unsupported`).
+	pub fn get_select_fd(&self) -> Result<Option<RawFd>> {
+		let mut fd: sys::Int = -1;
+		let status = unsafe { sys::sane_get_select_fd(self.handle, &mut fd as *mut sys::Int) };
+		match status {
+			sys::Status::Good => Ok(Some(fd as RawFd)),
+			sys::Status::Unsupported => Ok(None),
+			status => Err(Error(status)),
+		}
+	}
+
 	pub fn get_option(&self, opt: &DeviceOption) -> Result<DeviceOptionValue> {
 		let mut value = vec![0_u8; opt.size as usize];
 		let value_ptr = value.as_mut_ptr() as *mut c_void;
@@ -380,6 +432,123 @@ impl DeviceHandle {
 		}
 		Ok(opt_info)
 	}
+
+	/// Higher-level [`Self::set_option`]: accepts an ordinary Rust value,
+	/// converts it to the option's `SANE_Value_Type` (e.g. `f64` -> `Fixed`),
+	/// and enforces `opt.constraint` before making the FFI call — clamping to
+	/// `Range` (snapping to the nearest `quant` multiple), picking the
+	/// closest `WordList` entry, or rejecting a `StringList` value that isn't
+	/// a member. Check the returned `OptionInfo::INFO_INEXACT` to learn
+	/// whether the backend rounded the value further.
+	pub fn set_option_value(&self, opt: &DeviceOption, value: impl Into<Value>) -> Result<OptionInfo> {
+		let device_value = match (value.into(), opt.type_) {
+			(Value::Float(v), sys::ValueType::Fixed) => DeviceOptionValue::Fixed(Fixed::from_f64(v).0),
+			(Value::Int(v), sys::ValueType::Fixed) => DeviceOptionValue::Fixed(Fixed::from_f64(v as f64).0),
+			(Value::Float(v), sys::ValueType::Int) => DeviceOptionValue::Int(v.round() as i32),
+			(Value::Int(v), sys::ValueType::Int) => DeviceOptionValue::Int(v),
+			(Value::Bool(v), sys::ValueType::Bool) => DeviceOptionValue::Bool(v),
+			(Value::Str(v), sys::ValueType::String) => DeviceOptionValue::String(
+				CString::new(v).map_err(|_| Error(sys::Status::Inval))?,
+			),
+			_ => return Err(Error(sys::Status::Inval)),
+		};
+		let device_value = constrain(device_value, &opt.constraint)?;
+
+		self.set_option(opt, device_value)
+	}
+}
+
+/// Accepted by [`DeviceHandle::set_option_value`] so callers can pass
+/// ordinary Rust types instead of constructing a [`DeviceOptionValue`] and
+/// scaling fixed-point numbers by hand.
+#[derive(Debug, Clone)]
+pub enum Value {
+	Float(f64),
+	Int(i32),
+	Bool(bool),
+	Str(String),
+}
+
+impl From<f64> for Value {
+	fn from(v: f64) -> Self {
+		Value::Float(v)
+	}
+}
+
+impl From<i32> for Value {
+	fn from(v: i32) -> Self {
+		Value::Int(v)
+	}
+}
+
+impl From<bool> for Value {
+	fn from(v: bool) -> Self {
+		Value::Bool(v)
+	}
+}
+
+impl From<&str> for Value {
+	fn from(v: &str) -> Self {
+		Value::Str(v.to_owned())
+	}
+}
+
+fn constrain(value: DeviceOptionValue, constraint: &OptionConstraint) -> Result<DeviceOptionValue> {
+	match (value, constraint) {
+		(DeviceOptionValue::Int(v), OptionConstraint::Range { range, quant }) => {
+			Ok(DeviceOptionValue::Int(clamp_to_range(v, range, *quant)))
+		}
+		(DeviceOptionValue::Fixed(v), OptionConstraint::Range { range, quant }) => {
+			Ok(DeviceOptionValue::Fixed(clamp_to_range(v, range, *quant)))
+		}
+		(DeviceOptionValue::Int(v), OptionConstraint::WordList(words)) => {
+			Ok(DeviceOptionValue::Int(closest_word(v, words)))
+		}
+		(DeviceOptionValue::Fixed(v), OptionConstraint::WordList(words)) => {
+			Ok(DeviceOptionValue::Fixed(closest_word(v, words)))
+		}
+		(DeviceOptionValue::String(v), OptionConstraint::StringList(list)) => {
+			if list.iter().any(|allowed| allowed.as_c_str() == v.as_c_str()) {
+				Ok(DeviceOptionValue::String(v))
+			} else {
+				Err(Error(sys::Status::Inval))
+			}
+		}
+		(value, _) => Ok(value),
+	}
+}
+
+fn clamp_to_range(value: i32, range: &Range<i32>, quant: i32) -> i32 {
+	let clamped = value.clamp(range.start, range.end);
+	if quant <= 0 {
+		return clamped;
+	}
+	let steps = ((clamped - range.start) as f64 / quant as f64).round() as i32;
+	(range.start + steps * quant).clamp(range.start, range.end)
+}
+
+fn closest_word(value: i32, words: &[i32]) -> i32 {
+	*words
+		.iter()
+		.min_by_key(|&&word| (word - value).abs())
+		.unwrap_or(&value)
+}
+
+/// A SANE fixed-point number (`SANE_Fixed`): a signed 16.16 value, the
+/// integer part in the high 16 bits and the fraction in the low 16.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+	const SCALE: f64 = (1_i64 << 16) as f64;
+
+	pub fn to_f64(self) -> f64 {
+		self.0 as f64 / Self::SCALE
+	}
+
+	pub fn from_f64(value: f64) -> Self {
+		Fixed((value * Self::SCALE).round() as i32)
+	}
 }
 
 #[derive(Debug)]
@@ -425,6 +594,19 @@ pub enum OptionConstraint {
 	Range { range: Range<i32>, quant: i32 },
 }
 
+/// Result of a single [`DeviceHandle::read`] call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadOutcome {
+	/// `bytes` were read into the caller's buffer.
+	Data(usize),
+	/// Non-blocking mode only: no data is available yet. Not the end of the
+	/// frame — keep calling [`DeviceHandle::read`] once the select fd reports
+	/// readable.
+	Pending,
+	/// The current frame has ended.
+	Eof,
+}
+
 #[derive(Debug)]
 pub enum DeviceOptionValue {
 	Bool(bool),
@@ -434,3 +616,53 @@ pub enum DeviceOptionValue {
 	Button,
 	Group,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fixed_round_trips_through_f64() {
+		let fixed = Fixed::from_f64(3.5);
+		assert_eq!(fixed.0, 3 * 65536 + 65536 / 2);
+		assert_eq!(fixed.to_f64(), 3.5);
+	}
+
+	#[test]
+	fn fixed_from_f64_rounds_to_nearest() {
+		assert_eq!(Fixed::from_f64(-2.25).to_f64(), -2.25);
+		// 0.7 units of the last place rounds up to 1 rather than truncating to 0.
+		assert_eq!(Fixed::from_f64(0.7 / 65536.0).0, 1);
+	}
+
+	#[test]
+	fn clamp_to_range_stays_in_range_after_quant_snap() {
+		// range=0..10, quant=4, value=10: clamp -> 10, snap rounds up to 12,
+		// which must be clamped back down into range.
+		assert_eq!(clamp_to_range(10, &(0..10), 4), 10);
+	}
+
+	#[test]
+	fn clamp_to_range_snaps_to_nearest_quant_multiple() {
+		assert_eq!(clamp_to_range(7, &(0..20), 4), 8);
+		assert_eq!(clamp_to_range(5, &(0..20), 4), 4);
+	}
+
+	#[test]
+	fn clamp_to_range_clamps_out_of_bounds_values() {
+		assert_eq!(clamp_to_range(-5, &(0..20), 4), 0);
+		assert_eq!(clamp_to_range(100, &(0..20), 4), 20);
+	}
+
+	#[test]
+	fn clamp_to_range_passes_through_unquantized_values() {
+		assert_eq!(clamp_to_range(13, &(0..20), 0), 13);
+	}
+
+	#[test]
+	fn closest_word_picks_nearest_entry() {
+		let words = [0, 50, 100, 150];
+		assert_eq!(closest_word(60, &words), 50);
+		assert_eq!(closest_word(80, &words), 100);
+	}
+}
@@ -0,0 +1,112 @@
+//! A managed option cache that keeps itself consistent with the backend.
+//!
+//! `DeviceHandle::set_option` just returns the `OptionInfo` flags and leaves
+//! it to the caller to notice `INFO_RELOAD_OPTIONS`/`INFO_RELOAD_PARAMS` and
+//! re-fetch. [`Options`] does that automatically, so cached `option_idx` and
+//! `INACTIVE` capability state never goes stale after a set that invalidates
+//! the descriptor table.
+
+use crate::{DeviceHandle, DeviceOption, Error, OptionCapability, OptionInfo, Result, Value};
+use std::collections::HashMap;
+
+/// Owns a [`DeviceHandle`]'s option descriptors, keyed by name, and
+/// transparently reloads them after a `set` that reports
+/// `INFO_RELOAD_OPTIONS` (and the scan parameters after one that reports
+/// `INFO_RELOAD_PARAMS`).
+pub struct Options {
+	handle: DeviceHandle,
+	by_name: HashMap<String, DeviceOption>,
+	parameters: crate::sys::Parameters,
+}
+
+impl Options {
+	pub fn new(handle: DeviceHandle) -> Result<Self> {
+		let parameters = handle.get_parameters()?;
+		let by_name = Self::load(&handle)?;
+		Ok(Options {
+			handle,
+			by_name,
+			parameters,
+		})
+	}
+
+	fn load(handle: &DeviceHandle) -> Result<HashMap<String, DeviceOption>> {
+		Ok(handle
+			.get_options()?
+			.into_iter()
+			.map(|opt| (opt.name.to_string_lossy().into_owned(), opt))
+			.collect())
+	}
+
+	/// The descriptor for `name`, as of the last reload.
+	pub fn get(&self, name: &str) -> Option<&DeviceOption> {
+		self.by_name.get(name)
+	}
+
+	/// The scan parameters as of the last reload (re-fetched on
+	/// `INFO_RELOAD_PARAMS`).
+	pub fn parameters(&self) -> &crate::sys::Parameters {
+		&self.parameters
+	}
+
+	/// Looks `name` up and calls [`DeviceHandle::set_option_value`],
+	/// reloading the option list/parameters if the backend asks for it.
+	///
+	/// The value is already applied on the device once this returns `Ok`,
+	/// even if the reload itself failed — a failed cache refresh is logged
+	/// rather than turned into an `Err`, so callers can't mistake it for the
+	/// set (which may have side effects, e.g. calibration) not having
+	/// happened and retry it needlessly.
+	pub fn set(&mut self, name: &str, value: impl Into<Value>) -> Result<OptionInfo> {
+		let opt = self
+			.by_name
+			.get(name)
+			.ok_or(Error(crate::sys::Status::Inval))?;
+		let info = self.handle.set_option_value(opt, value)?;
+		if let Err(err) = self.reload(info) {
+			log::warn!("option '{}' was set but reloading the option cache failed: {:?}", name, err);
+		}
+		Ok(info)
+	}
+
+	/// Looks `name` up and calls [`DeviceHandle::set_option_auto`], reloading
+	/// the option list/parameters if the backend asks for it.
+	///
+	/// As with [`Self::set`], the set has already taken effect once this
+	/// returns `Ok`, regardless of whether the cache reload succeeded.
+	pub fn set_auto(&mut self, name: &str) -> Result<OptionInfo> {
+		let opt = self
+			.by_name
+			.get(name)
+			.ok_or(Error(crate::sys::Status::Inval))?;
+		let info = self.handle.set_option_auto(opt)?;
+		if let Err(err) = self.reload(info) {
+			log::warn!("option '{}' was auto-set but reloading the option cache failed: {:?}", name, err);
+		}
+		Ok(info)
+	}
+
+	fn reload(&mut self, info: OptionInfo) -> Result<()> {
+		if info.contains(OptionInfo::INFO_RELOAD_OPTIONS) {
+			self.by_name = Self::load(&self.handle)?;
+		}
+		if info.contains(OptionInfo::INFO_RELOAD_PARAMS) {
+			self.parameters = self.handle.get_parameters()?;
+		}
+		Ok(())
+	}
+
+	/// Options that can actually be changed right now: `SOFT_SELECT` is set
+	/// and `INACTIVE` isn't, per `OptionCapability`.
+	pub fn settable(&self) -> impl Iterator<Item = &DeviceOption> {
+		self.by_name.values().filter(|opt| {
+			opt.cap.contains(OptionCapability::SOFT_SELECT)
+				&& !opt.cap.contains(OptionCapability::INACTIVE)
+		})
+	}
+
+	/// Releases the underlying handle.
+	pub fn into_handle(self) -> DeviceHandle {
+		self.handle
+	}
+}
@@ -0,0 +1,90 @@
+//! Async scanning driven off the select fd exposed by `sane_get_select_fd`.
+//!
+//! This module only exists with the `tokio` feature enabled.
+
+use crate::{DeviceHandle, Error, ReadOutcome, Result};
+use bytes::Bytes;
+use futures_core::Stream;
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::unix::AsyncFd;
+
+/// Drives a [`DeviceHandle`] from an async executor instead of blocking a
+/// thread for the duration of the scan.
+///
+/// Construction starts the scan (`sane_start`), switches the handle into
+/// non-blocking mode and registers its select fd with tokio's reactor;
+/// polling the [`Stream`] impl then reads chunks of the frame as they become
+/// available.
+pub struct Scan {
+	handle: DeviceHandle,
+	async_fd: AsyncFd<RawFd>,
+	buf: Vec<u8>,
+	parameters: crate::sys::Parameters,
+}
+
+impl Scan {
+	/// Starts a scan on `handle`, switches it to non-blocking mode and
+	/// starts driving it from the current tokio reactor.
+	///
+	/// `sane_set_io_mode` is only valid once a scan is in progress, so this
+	/// calls `start_scan` itself — `handle` must not already be mid-scan.
+	pub fn new(mut handle: DeviceHandle) -> Result<Self> {
+		let parameters = handle.start_scan()?;
+		handle.set_io_mode(true)?;
+		let fd = handle
+			.get_select_fd()?
+			.ok_or(Error(crate::sys::Status::Unsupported))?;
+		let async_fd = AsyncFd::new(fd).map_err(|_| Error(crate::sys::Status::IoError))?;
+		Ok(Scan {
+			handle,
+			async_fd,
+			buf: vec![0_u8; 1024 * 1024],
+			parameters,
+		})
+	}
+
+	/// The parameters of the frame this `Scan` is reading, as reported by
+	/// `start_scan`.
+	pub fn parameters(&self) -> &crate::sys::Parameters {
+		&self.parameters
+	}
+
+	/// Polls for the next chunk of scan data, returning `None` at the end of
+	/// the frame.
+	pub fn poll_read(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Bytes>>> {
+		loop {
+			let mut guard = match self.async_fd.poll_read_ready(cx) {
+				Poll::Ready(Ok(guard)) => guard,
+				Poll::Ready(Err(_)) => return Poll::Ready(Err(Error(crate::sys::Status::IoError))),
+				Poll::Pending => return Poll::Pending,
+			};
+
+			match self.handle.read(&mut self.buf) {
+				Ok(ReadOutcome::Data(written)) => {
+					return Poll::Ready(Ok(Some(Bytes::copy_from_slice(&self.buf[..written]))))
+				}
+				Ok(ReadOutcome::Eof) => return Poll::Ready(Ok(None)),
+				Ok(ReadOutcome::Pending) => {
+					guard.clear_ready();
+					continue;
+				}
+				Err(err) => return Poll::Ready(Err(err)),
+			}
+		}
+	}
+}
+
+impl Stream for Scan {
+	type Item = Result<Bytes>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		match self.get_mut().poll_read(cx) {
+			Poll::Ready(Ok(Some(bytes))) => Poll::Ready(Some(Ok(bytes))),
+			Poll::Ready(Ok(None)) => Poll::Ready(None),
+			Poll::Ready(Err(err)) => Poll::Ready(Some(Err(err))),
+			Poll::Pending => Poll::Pending,
+		}
+	}
+}